@@ -58,9 +58,467 @@ pub fn rust_release_mode() -> bool {
     cfg!(not(debug_assertions))
 }
 
-use anyhow::{anyhow, Result};
-use std::process::Command;
-use std::fs;
+// Whether the current process already holds elevated privileges, so callers
+// can skip the pkexec/sudo dance entirely when it's unnecessary.
+pub enum RunningAs {
+    Root,
+    Administrator,
+    User,
+}
+
+// Checks the effective uid via `libc::geteuid()` on Unix, and the process
+// token's elevation state on Windows, so the Dart side gets one notion of
+// "already privileged" regardless of platform.
+pub fn running_as() -> RunningAs {
+    #[cfg(unix)]
+    {
+        if unsafe { libc::geteuid() } == 0 {
+            return RunningAs::Root;
+        }
+        RunningAs::User
+    }
+    #[cfg(windows)]
+    {
+        if windows_is_elevated() {
+            return RunningAs::Administrator;
+        }
+        RunningAs::User
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        RunningAs::User
+    }
+}
+
+#[cfg(windows)]
+fn windows_is_elevated() -> bool {
+    is_elevated::is_elevated()
+}
+
+// Detect-then-reexec: if we're not already privileged, relaunch the current
+// binary under the first available elevation backend, preserving argv.
+// Returns whether elevation was attempted, so the caller can tell "already
+// root" apart from "just reran under sudo".
+pub fn escalate_if_needed() -> Result<bool> {
+    if !matches!(running_as(), RunningAs::User) {
+        return Ok(false);
+    }
+
+    let exe = std::env::current_exe()?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    for backend in ["pkexec", "sudo"] {
+        let available = Command::new("which")
+            .arg(backend)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false);
+        if !available {
+            continue;
+        }
+
+        let status = Command::new(backend).arg(&exe).args(&args).status()?;
+        if status.success() {
+            return Ok(true);
+        }
+        return Err(ElevationError::CommandFailed {
+            code: status.code().unwrap_or(-1),
+            stderr: String::new(),
+        });
+    }
+
+    Err(ElevationError::BackendNotFound {
+        backend: "pkexec/sudo".to_string(),
+    })
+}
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use thiserror::Error;
+
+// Mirrors the possible ways privilege elevation can fail, so the Dart side
+// gets a tagged union instead of an opaque message and can branch on cause
+// (e.g. re-prompt for a password only on `PasswordRequired`).
+#[derive(Error, Debug)]
+pub enum ElevationError {
+    #[error("permission denied")]
+    PermissionDenied,
+    #[error("a password is required to elevate privileges")]
+    PasswordRequired,
+    #[error("elevation backend not found: {backend}")]
+    BackendNotFound { backend: String },
+    #[error("elevated command failed with code {code}: {stderr}")]
+    CommandFailed { code: i32, stderr: String },
+    #[error("elevation is not supported on this platform")]
+    UnsupportedPlatform,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+type Result<T> = std::result::Result<T, ElevationError>;
+
+// A single command to run as root, decoupled from any particular elevation
+// backend so callers aren't locked into listing `/root` specifically.
+#[derive(Debug, Clone)]
+pub struct PrivilegedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    // Fed to the child's stdin when a backend needs it (e.g. a sudo password).
+    pub stdin: Option<String>,
+}
+
+impl PrivilegedCommand {
+    pub fn new(program: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+            stdin: None,
+        }
+    }
+
+    pub fn with_stdin(mut self, stdin: impl Into<String>) -> Self {
+        self.stdin = Some(stdin.into());
+        self
+    }
+}
+
+pub struct CommandOutput {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+// Implemented by each elevation mechanism the crate knows how to shell out
+// to. `name()` doubles as the binary to probe for on `PATH` by default.
+trait Elevator {
+    fn name(&self) -> &'static str;
+    fn is_available(&self) -> bool {
+        binary_on_path(self.name())
+    }
+    fn run(&self, cmd: &PrivilegedCommand) -> Result<CommandOutput>;
+}
+
+fn binary_on_path(bin: &str) -> bool {
+    Command::new("which")
+        .arg(bin)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+// Quotes an argument for inclusion in a shell one-liner, the way the
+// `osascript "do shell script"` backend needs its command string built.
+#[cfg(target_os = "macos")]
+fn shell_escape(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+// Writes a password (plus trailing newline) to a child's stdin, tolerating
+// `BrokenPipe`: sudo may already be satisfied by cached/passwordless
+// credentials and exit without ever reading stdin, which must not be
+// reported as a failure when the command itself went on to succeed.
+fn feed_stdin(stdin: &mut std::process::ChildStdin, password: &str) -> Result<()> {
+    match stdin.write_all(format!("{}\n", password).as_bytes()) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn output_or_failed(output: std::process::Output) -> Result<CommandOutput> {
+    if output.status.success() {
+        Ok(CommandOutput {
+            stdout: output.stdout,
+            stderr: output.stderr,
+        })
+    } else {
+        Err(ElevationError::CommandFailed {
+            code: output.status.code().unwrap_or(-1),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+struct PkexecElevator;
+
+impl Elevator for PkexecElevator {
+    fn name(&self) -> &'static str {
+        "pkexec"
+    }
+
+    fn run(&self, cmd: &PrivilegedCommand) -> Result<CommandOutput> {
+        let output = Command::new("pkexec")
+            .arg(&cmd.program)
+            .args(&cmd.args)
+            .output()?;
+        output_or_failed(output)
+    }
+}
+
+struct SudoElevator;
+
+impl Elevator for SudoElevator {
+    fn name(&self) -> &'static str {
+        "sudo"
+    }
+
+    fn run(&self, cmd: &PrivilegedCommand) -> Result<CommandOutput> {
+        match &cmd.stdin {
+            // A password was supplied: pipe it over stdin the same way
+            // chunk0-4's `SudoLsMethod` does, so `-S` actually has something
+            // to read instead of hitting a closed pipe.
+            Some(password) => {
+                let mut child = Command::new("sudo")
+                    .arg("-S")
+                    .arg("-p")
+                    .arg("")
+                    .arg(&cmd.program)
+                    .args(&cmd.args)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()?;
+
+                {
+                    let stdin = child.stdin.as_mut().expect("child stdin was piped");
+                    feed_stdin(stdin, password)?;
+                }
+
+                output_or_failed(child.wait_with_output()?)
+            }
+            // No password supplied: drop `-S` and let sudo fall back to its
+            // askpass helper or an inherited tty prompt.
+            None => {
+                let output = Command::new("sudo")
+                    .arg(&cmd.program)
+                    .args(&cmd.args)
+                    .output()?;
+                output_or_failed(output)
+            }
+        }
+    }
+}
+
+struct DoasElevator;
+
+impl Elevator for DoasElevator {
+    fn name(&self) -> &'static str {
+        "doas"
+    }
+
+    fn run(&self, cmd: &PrivilegedCommand) -> Result<CommandOutput> {
+        let output = Command::new("doas")
+            .arg(&cmd.program)
+            .args(&cmd.args)
+            .output()?;
+        output_or_failed(output)
+    }
+}
+
+struct SuElevator;
+
+impl Elevator for SuElevator {
+    fn name(&self) -> &'static str {
+        "su"
+    }
+
+    fn run(&self, cmd: &PrivilegedCommand) -> Result<CommandOutput> {
+        let mut shell_cmd = cmd.program.clone();
+        for arg in &cmd.args {
+            shell_cmd.push(' ');
+            shell_cmd.push_str(arg);
+        }
+        let output = Command::new("su").arg("-c").arg(shell_cmd).output()?;
+        output_or_failed(output)
+    }
+}
+
+#[cfg(windows)]
+struct RunasElevator;
+
+#[cfg(windows)]
+impl Elevator for RunasElevator {
+    fn name(&self) -> &'static str {
+        "runas"
+    }
+
+    fn is_available(&self) -> bool {
+        // Not a `PATH` binary: it drives the Windows "runas" verb via
+        // ShellExecute, which is always present on Windows.
+        true
+    }
+
+    fn run(&self, cmd: &PrivilegedCommand) -> Result<CommandOutput> {
+        let status = runas::Command::new(&cmd.program)
+            .args(&cmd.args)
+            .gui(true)
+            .status()?;
+        if status.success() {
+            Ok(CommandOutput {
+                stdout: Vec::new(),
+                stderr: Vec::new(),
+            })
+        } else {
+            Err(ElevationError::CommandFailed {
+                code: status.code().unwrap_or(-1),
+                stderr: String::new(),
+            })
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+struct OsascriptElevator;
+
+#[cfg(target_os = "macos")]
+impl Elevator for OsascriptElevator {
+    fn name(&self) -> &'static str {
+        "osascript"
+    }
+
+    fn run(&self, cmd: &PrivilegedCommand) -> Result<CommandOutput> {
+        let mut shell_cmd = shell_escape(&cmd.program);
+        for arg in &cmd.args {
+            shell_cmd.push(' ');
+            shell_cmd.push_str(&shell_escape(arg));
+        }
+        let escaped = shell_cmd.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!("do shell script \"{}\" with administrator privileges", escaped);
+        let output = Command::new("osascript").arg("-e").arg(script).output()?;
+        output_or_failed(output)
+    }
+}
+
+// Picks the elevation backends that make sense for a given platform, so
+// `run_elevated` exposes the same API everywhere while using the native
+// prompt on each target (UAC on Windows, the `osascript` auth dialog on
+// macOS, pkexec/sudo/doas/su on Unix and Android).
+fn elevators_for_platform(target: Platform) -> Vec<Box<dyn Elevator>> {
+    match target {
+        #[cfg(windows)]
+        Platform::Windows => vec![Box::new(RunasElevator)],
+        #[cfg(not(windows))]
+        Platform::Windows => vec![],
+
+        #[cfg(target_os = "macos")]
+        Platform::MacApple | Platform::MacIntel => {
+            vec![Box::new(OsascriptElevator), Box::new(SudoElevator)]
+        }
+        #[cfg(not(target_os = "macos"))]
+        Platform::MacApple | Platform::MacIntel => vec![],
+
+        Platform::Unix | Platform::Android => vec![
+            Box::new(PkexecElevator),
+            Box::new(SudoElevator),
+            Box::new(DoasElevator),
+            Box::new(SuElevator),
+        ],
+        Platform::Ios | Platform::Wasm | Platform::Unknown => vec![],
+    }
+}
+
+// Names the elevation backends available on the current platform, in the
+// order `run_elevated` tries them. `Elevator` itself stays private, but
+// callers like the CLI still need something to list/validate `--backend`
+// against without reaching into the trait.
+pub fn available_backends() -> Vec<&'static str> {
+    elevators_for_platform(platform())
+        .iter()
+        .map(|e| e.name())
+        .collect()
+}
+
+// Runs `cmd` as root, trying `preferred_backends` in order (falling back to
+// every backend available on the current platform if empty) and returning
+// the first one that is present and succeeds.
+pub fn run_elevated(cmd: PrivilegedCommand, preferred_backends: &[&str]) -> Result<CommandOutput> {
+    let elevators = elevators_for_platform(platform());
+    if elevators.is_empty() {
+        return Err(ElevationError::UnsupportedPlatform);
+    }
+
+    let candidates: Vec<&dyn Elevator> = if preferred_backends.is_empty() {
+        elevators.iter().map(|e| e.as_ref()).collect()
+    } else {
+        preferred_backends
+            .iter()
+            .filter_map(|name| elevators.iter().find(|e| e.name() == *name))
+            .map(|e| e.as_ref())
+            .collect()
+    };
+
+    // Track the last real failure so a present-but-failing backend reports
+    // its actual cause (e.g. `CommandFailed` with its stderr) instead of a synthesized
+    // `BackendNotFound` once every candidate has been tried.
+    let mut last_err = None;
+    for elevator in candidates {
+        if !elevator.is_available() {
+            continue;
+        }
+        match elevator.run(&cmd) {
+            Ok(output) => return Ok(output),
+            Err(err) => last_err = Some(err),
+        }
+    }
+
+    Err(last_err.unwrap_or(ElevationError::BackendNotFound {
+        backend: preferred_backends.join(", "),
+    }))
+}
+
+// One parsed line of `ls -la` output, so the Dart side can render columns,
+// sort by size, and icon directories without slicing strings itself.
+#[derive(serde::Serialize)]
+pub struct DirEntry {
+    pub permissions: String,
+    pub links: u32,
+    pub owner: String,
+    pub group: String,
+    pub size: u64,
+    pub modified: String,
+    pub name: String,
+    pub is_dir: bool,
+}
+
+// Parses a single `ls -la` line, e.g.
+// `drwxr-xr-x  2 root root 4096 Jan  1 12:00 root`. Returns `None` for lines
+// that don't have enough fields (the leading `total N` header, blank lines).
+fn parse_ls_line(line: &str) -> Option<DirEntry> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 9 {
+        return None;
+    }
+
+    let permissions = fields[0].to_string();
+    let is_dir = permissions.starts_with('d');
+    let links = fields[1].parse().ok()?;
+    let owner = fields[2].to_string();
+    let group = fields[3].to_string();
+    let size = fields[4].parse().ok()?;
+    let modified = format!("{} {} {}", fields[5], fields[6], fields[7]);
+    let name = fields[8..].join(" ");
+
+    Some(DirEntry {
+        permissions,
+        links,
+        owner,
+        group,
+        size,
+        modified,
+        name,
+        is_dir,
+    })
+}
+
+// Parses the full output of `ls -la`, skipping the `total N` header. Public
+// so the standalone CLI in `src/bin` can reuse it over arbitrary `run_elevated`
+// output, not just `ls_with_polkit`/`ls_with_sudo`.
+pub fn parse_ls_output(lines: &[String]) -> Vec<DirEntry> {
+    lines
+        .iter()
+        .filter(|line| !line.starts_with("total "))
+        .filter_map(|line| parse_ls_line(line))
+        .collect()
+}
 
 trait LsRootMethod {
     fn execute(&self) -> Result<Vec<String>>;
@@ -85,61 +543,74 @@ impl LsRootMethod for PkexecLsMethod {
                 // check if the command was successful
                 if output.status.success() {
                     // convert the output to a string and return it
-                    let output_str = String::from_utf8(output.stdout)?;
+                    let output_str = String::from_utf8_lossy(&output.stdout);
                     return Ok(output_str.lines().map(String::from).collect());
                 }
-                Err(anyhow!("Permission Denied"))
+                Err(ElevationError::PermissionDenied)
             }
-            Err(_) => Err(anyhow!("Failed to elevate privileges with pkexec method.")),
+            Err(_) => Err(ElevationError::BackendNotFound {
+                backend: "pkexec".to_string(),
+            }),
         }
     }
 }
 
 impl LsRootMethod for SudoLsMethod {
     fn execute(&self) -> Result<Vec<String>> {
-        // run echo $password | sudo -S ls -la /root and save the output to a file
-        let password = &self.password;
-        let echo_cmd = format!("echo {}", password);
-        let output = Command::new("sh")
-            .arg("-c")
-            .arg(format!(
-                "{} | sudo -S ls -la /root > /tmp/result.txt",
-                echo_cmd
-            ))
-            .output()
-            .expect("Failed to elevate privileges with sudo.");
+        // Spawn sudo directly (no `sh -c`, no shell-visible echo) and feed the
+        // password over the child's stdin pipe so it never touches the
+        // process table, shell history, or disk.
+        let mut child = Command::new("sudo")
+            .arg("-S")
+            .arg("-p")
+            .arg("")
+            .arg("ls")
+            .arg("-la")
+            .arg("/root")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
 
-        // check if the command was successful and return the output
-        if output.status.success() {
-            let output = fs::read_to_string("/tmp/result.txt").expect("Failed to read result file");
+        {
+            let stdin = child.stdin.as_mut().expect("child stdin was piped");
+            feed_stdin(stdin, &self.password)?;
+        }
 
-            return Ok(output.lines().map(String::from).collect());
+        let output = child.wait_with_output()?;
+
+        if output.status.success() {
+            let output_str = String::from_utf8_lossy(&output.stdout);
+            return Ok(output_str.lines().map(String::from).collect());
         }
-        Err(anyhow!("Password required"))
+        Err(ElevationError::PasswordRequired)
     }
 }
 
 // with pollkit method
-pub fn ls_with_polkit() -> Result<Vec<String>> {
+pub fn ls_with_polkit() -> Result<Vec<DirEntry>> {
     // create a vector of methods
     let methods: Vec<Box<dyn LsRootMethod>> = vec![Box::new(PkexecLsMethod)];
 
-    // try to execute each method and return the result if successful
+    // try to execute each method and return the result if successful, keeping
+    // the real cause around so a present-but-failing backend reports
+    // `PermissionDenied`/etc. instead of a synthesized `BackendNotFound`.
+    let mut last_err = None;
     for method in methods {
         match method.execute() {
-            Ok(result) => return Ok(result),
-            Err(_) => continue,
+            Ok(result) => return Ok(parse_ls_output(&result)),
+            Err(err) => last_err = Some(err),
         };
     }
 
-    Err(anyhow!("Failed to elevate privileges with polkit."))
+    Err(last_err.unwrap_or(ElevationError::BackendNotFound {
+        backend: "pkexec".to_string(),
+    }))
 }
 
 // with sudo and password method
-pub fn ls_with_sudo(password: String) -> Result<Vec<String>> {
+pub fn ls_with_sudo(password: String) -> Result<Vec<DirEntry>> {
     // create a vector of methods and add the sudo method
     let method = SudoLsMethod { password };
-    method
-        .execute()
-        .map_err(|_| anyhow!("Failed to elevate privileges with sudo."))
+    method.execute().map(|lines| parse_ls_output(&lines))
 }
@@ -0,0 +1,105 @@
+// Headless entry point over the same `run_elevated` backends the Flutter
+// bridge uses, so they can be exercised in CI or by hand without building
+// the Dart app. The backend registry itself (`Elevator`) stays private to
+// the library; this CLI only sees the names `available_backends()` exposes.
+
+use clap::{Parser, ValueEnum};
+use native::api::{self, PrivilegedCommand};
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Backend {
+    Pkexec,
+    Sudo,
+    Doas,
+}
+
+impl Backend {
+    fn as_str(self) -> &'static str {
+        match self {
+            Backend::Pkexec => "pkexec",
+            Backend::Sudo => "sudo",
+            Backend::Doas => "doas",
+        }
+    }
+}
+
+/// List a directory as root through one of the crate's elevation backends.
+#[derive(Parser)]
+struct Cli {
+    /// Elevation backend to use; tries every backend available on this
+    /// platform if omitted.
+    #[arg(long, value_enum)]
+    backend: Option<Backend>,
+
+    /// Directory to list.
+    #[arg(long, default_value = "/root")]
+    path: String,
+
+    /// Print the parsed entries as JSON instead of a table.
+    #[arg(long)]
+    json: bool,
+
+    /// Print whether this binary was built in debug or release mode, then exit.
+    #[arg(long)]
+    variant: bool,
+
+    /// List the elevation backends available on this platform, then exit.
+    #[arg(long)]
+    list_backends: bool,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    if cli.variant {
+        println!("{}", if api::rust_release_mode() { "release" } else { "debug" });
+        return;
+    }
+
+    if cli.list_backends {
+        for name in api::available_backends() {
+            println!("{name}");
+        }
+        return;
+    }
+
+    let preferred: Vec<&str> = cli.backend.map(|b| vec![b.as_str()]).unwrap_or_default();
+    let cmd = PrivilegedCommand::new("ls", vec!["-la".to_string(), cli.path]);
+
+    let output = match api::run_elevated(cmd, &preferred) {
+        Ok(output) => output,
+        Err(err) => {
+            eprintln!("error: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let lines: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(String::from)
+        .collect();
+    let entries = api::parse_ls_output(&lines);
+
+    if cli.json {
+        match serde_json::to_string_pretty(&entries) {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("error: failed to serialize entries: {err}");
+                std::process::exit(1);
+            }
+        }
+    } else {
+        for entry in &entries {
+            println!(
+                "{} {:>4} {:<8} {:<8} {:>10} {} {}",
+                entry.permissions,
+                entry.links,
+                entry.owner,
+                entry.group,
+                entry.size,
+                entry.modified,
+                entry.name
+            );
+        }
+    }
+}